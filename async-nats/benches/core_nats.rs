@@ -3,6 +3,7 @@ use std::time::Duration;
 use bytes::Bytes;
 use criterion::{criterion_group, criterion_main, Criterion};
 use futures::stream::StreamExt;
+use pprof::criterion::{Output, PProfProfiler};
 
 static MSG: &[u8] = &[22; 32768];
 
@@ -48,7 +49,7 @@ pub fn publish(c: &mut Criterion) {
                 let rt = tokio::runtime::Runtime::new().unwrap();
                 let nc = rt.block_on(async {
                     let nc = async_nats::connect(server.client_url()).await.unwrap();
-                    nc.publish("data".to_string(), "data".into()).await.unwrap();
+                    nc.publish("data".to_string().into(), "data".into()).await.unwrap();
                     nc.flush().await.unwrap();
                     nc
                 });
@@ -66,6 +67,79 @@ pub fn publish(c: &mut Criterion) {
     messages_group.finish();
 }
 
+/// Exercises the two throughput-oriented alternatives to calling `publish` in a loop:
+/// [`async_nats::Client::publish_batch`], which coalesces a caller-sized batch into one
+/// write, and [`async_nats::ConnectOptions::publish_auto_flush_bytes`]/
+/// [`publish_auto_flush_messages`](async_nats::ConnectOptions::publish_auto_flush_messages),
+/// which coalesce transparently based on configured thresholds.
+pub fn publish_batch(c: &mut Criterion) {
+    let messages_amount = 500_000;
+    let batch_size = 256;
+    let server = nats_server::run_basic_server();
+
+    let mut batch_group = c.benchmark_group("async-nats: publish_batch throughput");
+    batch_group.warm_up_time(std::time::Duration::from_secs(1));
+
+    for &size in [32, 1024, 8192].iter() {
+        batch_group.throughput(criterion::Throughput::Bytes(size as u64 * messages_amount));
+        batch_group.bench_with_input(
+            criterion::BenchmarkId::from_parameter(size),
+            &size,
+            |b, _| {
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                let nc =
+                    rt.block_on(async { async_nats::connect(server.client_url()).await.unwrap() });
+
+                b.to_async(rt).iter(move || {
+                    let nc = nc.clone();
+                    async move {
+                        publish_messages_batched(
+                            nc,
+                            Bytes::from_static(&MSG[..size]),
+                            messages_amount,
+                            batch_size,
+                        )
+                        .await
+                    }
+                });
+            },
+        );
+    }
+    batch_group.finish();
+
+    let mut auto_flush_group = c.benchmark_group("async-nats: publish_auto_flush throughput");
+    auto_flush_group.warm_up_time(std::time::Duration::from_secs(1));
+
+    for &size in [32, 1024, 8192].iter() {
+        auto_flush_group.throughput(criterion::Throughput::Bytes(size as u64 * messages_amount));
+        auto_flush_group.bench_with_input(
+            criterion::BenchmarkId::from_parameter(size),
+            &size,
+            |b, _| {
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                let url = server.client_url();
+                let nc = rt.block_on(async {
+                    async_nats::ConnectOptions::new()
+                        .publish_auto_flush_bytes(64 * 1024)
+                        .publish_auto_flush_messages(256)
+                        .connect(url)
+                        .await
+                        .unwrap()
+                });
+
+                b.to_async(rt).iter(move || {
+                    let nc = nc.clone();
+                    async move {
+                        publish_messages(nc, Bytes::from_static(&MSG[..size]), messages_amount)
+                            .await
+                    }
+                });
+            },
+        );
+    }
+    auto_flush_group.finish();
+}
+
 pub fn subscribe(c: &mut Criterion) {
     let server = nats_server::run_basic_server();
     let messages_per_subscribe = 500_000;
@@ -98,13 +172,16 @@ pub fn subscribe(c: &mut Criterion) {
                             started.send(()).unwrap();
                             loop {
                                 client
-                                    .publish("bench".to_string(), Bytes::from_static(&MSG[..size]))
+                                    .publish(
+                                        "bench".to_string().into(),
+                                        Bytes::from_static(&MSG[..size]),
+                                    )
                                     .await
                                     .ok();
                             }
                         }
                     });
-                    nc.publish("data".to_string(), "data".into()).await.unwrap();
+                    nc.publish("data".to_string().into(), "data".into()).await.unwrap();
                     nc.flush().await.unwrap();
                     ready.await.unwrap();
                     (nc, handle)
@@ -178,6 +255,67 @@ pub fn request(c: &mut Criterion) {
     subscribe_amount_group.finish();
 }
 
+/// Unlike `request`, which amortizes connection overhead over many requests per iteration,
+/// this measures the end-to-end time of a single outstanding request/reply round trip.
+pub fn request_latency(c: &mut Criterion) {
+    let server = nats_server::run_basic_server();
+
+    let mut latency_group = c.benchmark_group("async-nats: request latency");
+
+    for &size in [32, 1024, 8192].iter() {
+        let url = server.client_url();
+        latency_group.bench_with_input(
+            criterion::BenchmarkId::from_parameter(size),
+            &size,
+            move |b, _| {
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                let url = url.clone();
+                let (nc, handle) = rt.block_on(async move {
+                    let nc = async_nats::ConnectOptions::new()
+                        .connect(url.clone())
+                        .await
+                        .unwrap();
+                    let (started, ready) = tokio::sync::oneshot::channel();
+                    let handle = tokio::task::spawn({
+                        async move {
+                            let client = async_nats::ConnectOptions::new()
+                                .connect(url)
+                                .await
+                                .unwrap();
+
+                            let mut subscription =
+                                client.subscribe("bench-latency".into()).await.unwrap();
+                            started.send(()).unwrap();
+
+                            while let Some(request) = subscription.next().await {
+                                client
+                                    .publish(request.reply.unwrap(), "".into())
+                                    .await
+                                    .unwrap();
+                                client.flush().await.unwrap();
+                            }
+                        }
+                    });
+                    nc.flush().await.unwrap();
+                    ready.await.unwrap();
+                    (nc, handle)
+                });
+
+                b.to_async(rt).iter(move || {
+                    let nc = nc.clone();
+                    async move {
+                        nc.request("bench-latency".into(), Bytes::from_static(&MSG[..size]))
+                            .await
+                            .unwrap();
+                    }
+                });
+                handle.abort();
+            },
+        );
+    }
+    latency_group.finish();
+}
+
 async fn requests(nc: async_nats::Client, msg: Bytes, amount: u64) {
     for _i in 0..amount {
         nc.request("bench".into(), msg.clone()).await.unwrap();
@@ -192,6 +330,20 @@ async fn publish_messages(nc: async_nats::Client, msg: Bytes, amount: u64) {
     nc.flush().await.unwrap();
 }
 
+async fn publish_messages_batched(nc: async_nats::Client, msg: Bytes, amount: u64, batch_size: u64) {
+    let mut batch: Vec<(async_nats::Subject, Bytes)> = Vec::with_capacity(batch_size as usize);
+    for _i in 0..amount {
+        batch.push(("bench".into(), msg.clone()));
+        if batch.len() as u64 == batch_size {
+            nc.publish_batch(std::mem::take(&mut batch)).await.unwrap();
+        }
+    }
+    if !batch.is_empty() {
+        nc.publish_batch(batch).await.unwrap();
+    }
+    nc.flush().await.unwrap();
+}
+
 async fn subscribe_messages(nc: async_nats::Client, amount: u64) {
     let mut sub = nc.subscribe("bench".into()).await.unwrap();
     for _ in 0..amount {
@@ -199,5 +351,32 @@ async fn subscribe_messages(nc: async_nats::Client, amount: u64) {
     }
 }
 
-criterion_group!(benches, publish, subscribe);
+/// Builds the `Criterion` configuration shared by every benchmark group, wiring in the
+/// pprof profiler (`cargo bench -- --profile-time <secs>` then emits a flamegraph) and
+/// reading measurement time/sample size from the environment so CI can run a fast pass
+/// while local profiling runs can dial up the depth. The "publish throughput", "publish
+/// messages amount", "subscribe amount" and "request amount" groups set their own sample
+/// size of 10, which takes precedence over `NATS_BENCH_SAMPLE_SIZE`.
+fn configure_criterion() -> Criterion {
+    let measurement_time = std::env::var("NATS_BENCH_MEASUREMENT_TIME")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(5));
+    let sample_size = std::env::var("NATS_BENCH_SAMPLE_SIZE")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(10);
+
+    Criterion::default()
+        .measurement_time(measurement_time)
+        .sample_size(sample_size)
+        .with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)))
+}
+
+criterion_group! {
+    name = benches;
+    config = configure_criterion();
+    targets = publish, publish_batch, subscribe, request, request_latency
+}
 criterion_main!(benches);
@@ -0,0 +1,224 @@
+//! A load-generation tool reporting latency percentiles, modeled on tools like `nats-bench`
+//! from other NATS clients. Unlike the criterion benches in `benches/`, this binary is meant
+//! to be pointed at a real deployment for regression and capacity testing.
+//!
+//! ```text
+//! nats-bench --mode publish --workers 10 --iterations 10000 --size 128 --subject bench
+//! ```
+
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::StreamExt;
+use hdrhistogram::Histogram;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Publish,
+    Subscribe,
+    Request,
+}
+
+impl std::str::FromStr for Mode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "publish" => Ok(Mode::Publish),
+            "subscribe" => Ok(Mode::Subscribe),
+            "request" => Ok(Mode::Request),
+            other => Err(format!("unknown mode `{other}` (expected publish, subscribe or request)")),
+        }
+    }
+}
+
+struct Config {
+    url: String,
+    mode: Mode,
+    workers: u64,
+    iterations: u64,
+    size: usize,
+    subject: String,
+    shared_client: bool,
+}
+
+impl Config {
+    fn from_args() -> Self {
+        let mut config = Config {
+            url: "nats://127.0.0.1:4222".to_string(),
+            mode: Mode::Publish,
+            workers: 10,
+            iterations: 10_000,
+            size: 128,
+            subject: "nats-bench".to_string(),
+            shared_client: true,
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().unwrap_or_else(|| panic!("{arg} expects a value"));
+            match arg.as_str() {
+                "--url" => config.url = value(),
+                "--mode" => config.mode = value().parse().unwrap_or_else(|err| panic!("{err}")),
+                "--workers" => config.workers = value().parse().expect("--workers expects an integer"),
+                "--iterations" => {
+                    config.iterations = value().parse().expect("--iterations expects an integer")
+                }
+                "--size" => config.size = value().parse().expect("--size expects an integer"),
+                "--subject" => config.subject = value(),
+                "--separate-clients" => config.shared_client = false,
+                other => panic!("unknown argument `{other}`"),
+            }
+        }
+
+        config
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let config = Config::from_args();
+
+    let shared_client = if config.shared_client {
+        Some(
+            async_nats::connect(config.url.clone())
+                .await
+                .expect("failed to connect"),
+        )
+    } else {
+        None
+    };
+
+    // In request mode a responder must be running on `subject` for round trips to complete.
+    if config.mode == Mode::Request {
+        let responder = match &shared_client {
+            Some(client) => client.clone(),
+            None => async_nats::connect(config.url.clone())
+                .await
+                .expect("failed to connect"),
+        };
+        spawn_responder(responder, config.subject.clone());
+    }
+
+    let payload = Bytes::from(vec![7u8; config.size]);
+    let started = Instant::now();
+
+    let mut workers = Vec::with_capacity(config.workers as usize);
+    for worker_id in 0..config.workers {
+        let client = match &shared_client {
+            Some(client) => client.clone(),
+            None => async_nats::connect(config.url.clone())
+                .await
+                .expect("failed to connect"),
+        };
+        let subject = config.subject.clone();
+        let payload = payload.clone();
+        let mode = config.mode;
+        let iterations = config.iterations;
+        workers.push(tokio::spawn(async move {
+            run_worker(worker_id, client, mode, subject, payload, iterations).await
+        }));
+    }
+
+    let mut histogram = Histogram::<u64>::new(3).expect("failed to allocate histogram");
+    for worker in workers {
+        let worker_histogram = worker.await.expect("worker panicked");
+        histogram
+            .add(worker_histogram)
+            .expect("incompatible histograms");
+    }
+
+    let elapsed = started.elapsed();
+    print_report(&config, elapsed, &histogram);
+}
+
+async fn run_worker(
+    _worker_id: u64,
+    client: async_nats::Client,
+    mode: Mode,
+    subject: String,
+    payload: Bytes,
+    iterations: u64,
+) -> Histogram<u64> {
+    let subject: async_nats::Subject = subject.into();
+    let mut histogram = Histogram::<u64>::new(3).expect("failed to allocate histogram");
+
+    match mode {
+        Mode::Publish => {
+            for _ in 0..iterations {
+                let start = Instant::now();
+                client
+                    .publish(subject.clone(), payload.clone())
+                    .await
+                    .expect("publish failed");
+                client.flush().await.expect("flush failed");
+                record(&mut histogram, start.elapsed());
+            }
+        }
+        Mode::Subscribe => {
+            let mut subscriber = client
+                .subscribe(subject)
+                .await
+                .expect("subscribe failed");
+            for _ in 0..iterations {
+                let start = Instant::now();
+                subscriber.next().await.expect("subscription ended early");
+                record(&mut histogram, start.elapsed());
+            }
+        }
+        Mode::Request => {
+            for _ in 0..iterations {
+                let start = Instant::now();
+                client
+                    .request(subject.clone(), payload.clone())
+                    .await
+                    .expect("request failed");
+                record(&mut histogram, start.elapsed());
+            }
+        }
+    }
+
+    histogram
+}
+
+fn spawn_responder(client: async_nats::Client, subject: String) {
+    tokio::spawn(async move {
+        let mut subscriber = client
+            .subscribe(subject.into())
+            .await
+            .expect("responder subscribe failed");
+        while let Some(message) = subscriber.next().await {
+            if let Some(reply) = message.reply {
+                let _ = client.publish(reply, Bytes::new()).await;
+            }
+        }
+    });
+}
+
+fn record(histogram: &mut Histogram<u64>, latency: Duration) {
+    let micros = latency.as_micros().min(u64::MAX as u128) as u64;
+    let _ = histogram.record(micros.max(1));
+}
+
+fn print_report(config: &Config, elapsed: Duration, histogram: &Histogram<u64>) {
+    let total_messages = config.workers * config.iterations;
+    let messages_per_sec = total_messages as f64 / elapsed.as_secs_f64();
+
+    println!("mode:        {:?}", config.mode);
+    println!("workers:     {}", config.workers);
+    println!("iterations:  {} per worker", config.iterations);
+    println!("size:        {} bytes", config.size);
+    println!("elapsed:     {elapsed:?}");
+    println!("messages/sec: {messages_per_sec:.0}");
+    println!();
+    println!("{:>8} {:>10} {:>10}", "quantile", "latency", "unit");
+    for quantile in [0.50, 0.90, 0.99, 0.999] {
+        println!(
+            "{:>8} {:>10} {:>10}",
+            format!("p{}", quantile * 100.0),
+            histogram.value_at_quantile(quantile),
+            "us"
+        );
+    }
+    println!("{:>8} {:>10} {:>10}", "max", histogram.max(), "us");
+}
@@ -0,0 +1,284 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use futures::{future, StreamExt};
+use tokio::sync::{mpsc, oneshot, OnceCell};
+
+use crate::error::{Error, Result};
+use crate::message::{Message, StatusCode};
+use crate::mux::Mux;
+use crate::request_many::{RequestManyOptions, RequestManyStream};
+use crate::subject::Subject;
+use crate::subscriber::Subscriber;
+
+pub(crate) enum Command {
+    Publish {
+        subject: Subject,
+        payload: Bytes,
+        reply: Option<Subject>,
+    },
+    PublishBatch {
+        messages: Vec<(Subject, Bytes)>,
+    },
+    Subscribe {
+        sid: u64,
+        subject: Subject,
+        sender: mpsc::Sender<Message>,
+    },
+    Unsubscribe {
+        sid: u64,
+        max_msgs: Option<u64>,
+    },
+    Flush {
+        result: oneshot::Sender<()>,
+    },
+}
+
+/// A cheaply cloneable handle to a NATS connection managed in the background.
+#[derive(Clone)]
+pub struct Client {
+    pub(crate) sender: mpsc::Sender<Command>,
+    pub(crate) next_sid: Arc<AtomicU64>,
+    pub(crate) inbox_prefix: Arc<str>,
+    pub(crate) subscription_capacity: usize,
+    mux: Arc<OnceCell<Arc<Mux>>>,
+}
+
+impl Client {
+    pub(crate) fn new(
+        sender: mpsc::Sender<Command>,
+        inbox_prefix: String,
+        subscription_capacity: usize,
+    ) -> Self {
+        Client {
+            sender,
+            next_sid: Arc::new(AtomicU64::new(1)),
+            inbox_prefix: inbox_prefix.into(),
+            subscription_capacity,
+            mux: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// Returns the lazily-started shared reply inbox used by [`Client::request_batch`] and
+    /// [`Client::request_batch_with_timeout`], starting it on first use.
+    async fn mux(&self) -> Result<Arc<Mux>> {
+        self.mux
+            .get_or_try_init(|| Mux::start(self))
+            .await
+            .cloned()
+    }
+
+    /// Generates a new, unique inbox subject that can be used as a reply-to subject.
+    pub fn new_inbox(&self) -> Subject {
+        Subject::from(format!("{}.{}", self.inbox_prefix, unique_token()))
+    }
+
+    /// Publishes `payload` to `subject`.
+    pub async fn publish(&self, subject: Subject, payload: Bytes) -> Result<()> {
+        self.sender
+            .send(Command::Publish {
+                subject,
+                payload,
+                reply: None,
+            })
+            .await
+            .map_err(|_| Error::ConnectionClosed)
+    }
+
+    /// Publishes `payload` to `subject`, asking responders to reply to `reply`.
+    pub async fn publish_with_reply(
+        &self,
+        subject: Subject,
+        reply: Subject,
+        payload: Bytes,
+    ) -> Result<()> {
+        self.sender
+            .send(Command::Publish {
+                subject,
+                payload,
+                reply: Some(reply),
+            })
+            .await
+            .map_err(|_| Error::ConnectionClosed)
+    }
+
+    /// Publishes every `(subject, payload)` pair in one coalesced write, rather than the
+    /// per-message framing and syscall overhead of calling [`Client::publish`] in a loop.
+    /// See [`ConnectOptions::publish_auto_flush_bytes`](crate::options::ConnectOptions::publish_auto_flush_bytes)/
+    /// [`ConnectOptions::publish_auto_flush_messages`](crate::options::ConnectOptions::publish_auto_flush_messages)
+    /// for a threshold-based alternative that applies to ordinary `publish` calls as well.
+    pub async fn publish_batch(&self, messages: Vec<(Subject, Bytes)>) -> Result<()> {
+        self.sender
+            .send(Command::PublishBatch { messages })
+            .await
+            .map_err(|_| Error::ConnectionClosed)
+    }
+
+    /// Subscribes to `subject`, returning a [`Subscriber`] stream of matching messages.
+    pub async fn subscribe(&self, subject: Subject) -> Result<Subscriber> {
+        let sid = self.next_sid.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::channel(self.subscription_capacity);
+        self.sender
+            .send(Command::Subscribe {
+                sid,
+                subject,
+                sender,
+            })
+            .await
+            .map_err(|_| Error::ConnectionClosed)?;
+        Ok(Subscriber::new(sid, self.sender.clone(), receiver))
+    }
+
+    /// Flushes the outbound buffer, resolving once the server has acknowledged it.
+    pub async fn flush(&self) -> Result<()> {
+        let (result, done) = oneshot::channel();
+        self.sender
+            .send(Command::Flush { result })
+            .await
+            .map_err(|_| Error::ConnectionClosed)?;
+        done.await.map_err(|_| Error::ConnectionClosed)
+    }
+
+    /// Sends a request and waits for the first reply, failing fast if the server reports
+    /// that no responders are listening on `subject`.
+    pub async fn request(&self, subject: Subject, payload: Bytes) -> Result<Message> {
+        let reply = self.new_inbox();
+        let mut subscriber = self.subscribe(reply.clone()).await?;
+        subscriber.unsubscribe_after(1).await?;
+        self.publish_with_reply(subject, reply, payload).await?;
+        self.flush().await?;
+
+        match subscriber.next().await {
+            Some(message) if is_no_responders(&message) => Err(Error::NoResponders),
+            Some(message) => Ok(message),
+            None => Err(Error::ConnectionClosed),
+        }
+    }
+
+    /// Sends a request and returns a stream of every reply received, for use with
+    /// subjects that may have more than one responder (service discovery, fan-out).
+    ///
+    /// Uses [`RequestManyOptions::default`]; see [`Client::request_many_with_options`]
+    /// to customize how the stream decides it has heard from everyone it is going to.
+    pub async fn request_many(&self, subject: Subject, payload: Bytes) -> Result<RequestManyStream> {
+        self.request_many_with_options(subject, payload, RequestManyOptions::default())
+            .await
+    }
+
+    /// Like [`Client::request_many`], with explicit termination options.
+    pub async fn request_many_with_options(
+        &self,
+        subject: Subject,
+        payload: Bytes,
+        options: RequestManyOptions,
+    ) -> Result<RequestManyStream> {
+        let reply = self.new_inbox();
+        let subscriber = self.subscribe(reply.clone()).await?;
+        self.publish_with_reply(subject, reply, payload).await?;
+        self.flush().await?;
+        Ok(RequestManyStream::new(subscriber, options))
+    }
+
+    /// Fires every `(subject, payload)` request concurrently over this connection's muxed
+    /// reply inbox and collects the responses in the same order the requests were given,
+    /// mirroring the batched-request pattern used by JSON-RPC clients.
+    ///
+    /// Unlike calling [`Client::request`] in a loop (or `join_all`-ing it), every request in
+    /// the batch shares a single wildcard reply subscription, keyed by a per-request token,
+    /// instead of each opening and tearing down its own subscription.
+    pub async fn request_batch(
+        &self,
+        requests: impl IntoIterator<Item = (Subject, Bytes)>,
+    ) -> Vec<Result<Message>> {
+        let mux = match self.mux().await {
+            Ok(mux) => mux,
+            Err(_) => return requests.into_iter().map(|_| Err(Error::ConnectionClosed)).collect(),
+        };
+
+        let pending = self.send_batch(&mux, requests).await;
+        future::join_all(pending.into_iter().map(|entry| async move {
+            match entry {
+                Ok((_token, receiver)) => receiver.await.map_err(|_| Error::ConnectionClosed),
+                Err(err) => Err(err),
+            }
+        }))
+        .await
+    }
+
+    /// Like [`Client::request_batch`], but fails every request still outstanding once
+    /// `timeout` has elapsed, without disturbing requests that already received a reply.
+    pub async fn request_batch_with_timeout(
+        &self,
+        requests: impl IntoIterator<Item = (Subject, Bytes)>,
+        timeout: Duration,
+    ) -> Vec<Result<Message>> {
+        let mux = match self.mux().await {
+            Ok(mux) => mux,
+            Err(_) => return requests.into_iter().map(|_| Err(Error::ConnectionClosed)).collect(),
+        };
+
+        let pending = self.send_batch(&mux, requests).await;
+        future::join_all(pending.into_iter().map(|entry| async {
+            match entry {
+                Ok((token, receiver)) => match tokio::time::timeout(timeout, receiver).await {
+                    Ok(Ok(message)) => Ok(message),
+                    Ok(Err(_)) => Err(Error::ConnectionClosed),
+                    Err(_) => {
+                        mux.cancel(token);
+                        Err(Error::TimedOut)
+                    }
+                },
+                Err(err) => Err(err),
+            }
+        }))
+        .await
+    }
+
+    /// Reserves a mux route and publishes each request, returning one entry per request in
+    /// the same order, `Err` for ones that failed to even go out on the wire.
+    async fn send_batch(
+        &self,
+        mux: &Arc<Mux>,
+        requests: impl IntoIterator<Item = (Subject, Bytes)>,
+    ) -> Vec<Result<(u64, oneshot::Receiver<Message>)>> {
+        let mut pending = Vec::new();
+        for (subject, payload) in requests {
+            let (reply, token, receiver) = mux.reserve();
+            match self.publish_with_reply(subject, reply, payload).await {
+                Ok(()) => pending.push(Ok((token, receiver))),
+                Err(err) => {
+                    mux.cancel(token);
+                    pending.push(Err(err));
+                }
+            }
+        }
+
+        // A failed flush means we can't tell whether the queued PUBs reached the server, so
+        // don't leave their routes registered waiting for replies that may never come.
+        if self.flush().await.is_err() {
+            for entry in pending.iter_mut() {
+                if let Ok((token, _receiver)) = entry {
+                    mux.cancel(*token);
+                    *entry = Err(Error::ConnectionClosed);
+                }
+            }
+        }
+        pending
+    }
+}
+
+pub(crate) fn is_no_responders(message: &Message) -> bool {
+    message.status == Some(StatusCode::NO_RESPONDERS) && message.payload.is_empty()
+}
+
+fn unique_token() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let counter = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("{nanos:x}{counter:x}")
+}
@@ -0,0 +1,304 @@
+use std::collections::{HashMap, VecDeque};
+use std::io;
+
+use bytes::{Bytes, BytesMut};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::client::{Client, Command};
+use crate::message::{Message, StatusCode};
+use crate::options::ConnectOptions;
+use crate::subject::Subject;
+
+const COMMAND_CHANNEL_CAPACITY: usize = 1024;
+
+/// Connects to `addr` and spawns the background task that owns the socket, dispatching
+/// commands from [`Client`] and incoming messages to their subscribers.
+pub(crate) async fn connect(addr: String, options: ConnectOptions) -> io::Result<Client> {
+    let host = addr.trim_start_matches("nats://");
+    let stream = TcpStream::connect(host).await?;
+    let (read_half, mut write_half) = stream.into_split();
+    let mut reader = BufReader::new(read_half);
+
+    // The initial INFO line is not yet parsed; this client does not negotiate TLS or
+    // authentication.
+    let mut info_line = String::new();
+    reader.read_line(&mut info_line).await?;
+    write_half
+        .write_all(b"CONNECT {\"verbose\":false,\"pedantic\":false,\"headers\":true,\"no_responders\":true}\r\n")
+        .await?;
+
+    let (sender, mut commands) = mpsc::channel::<Command>(COMMAND_CHANNEL_CAPACITY);
+    let mut writer = CoalescingWriter::new(write_half, options.auto_flush_bytes, options.auto_flush_messages);
+
+    tokio::spawn(async move {
+        let mut subscriptions: HashMap<u64, mpsc::Sender<Message>> = HashMap::new();
+        let mut pending_flushes: VecDeque<oneshot::Sender<()>> = VecDeque::new();
+
+        loop {
+            tokio::select! {
+                command = commands.recv() => {
+                    let Some(command) = command else { break };
+                    if handle_command(command, &mut writer, &mut subscriptions, &mut pending_flushes).await.is_err() {
+                        break;
+                    }
+                }
+                op = read_server_op(&mut reader) => {
+                    match op {
+                        Ok(Some(ServerOp::Message { sid, message })) => {
+                            if let Some(sender) = subscriptions.get(&sid) {
+                                let _ = sender.send(message).await;
+                            }
+                        }
+                        Ok(Some(ServerOp::Ping)) => {
+                            if writer.write_immediate(b"PONG\r\n").await.is_err() {
+                                break;
+                            }
+                        }
+                        Ok(Some(ServerOp::Pong)) => {
+                            if let Some(result) = pending_flushes.pop_front() {
+                                let _ = result.send(());
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(Client::new(
+        sender,
+        options.inbox_prefix,
+        options.subscription_capacity,
+    ))
+}
+
+/// Accumulates outbound frames and writes them to the socket in a single syscall once
+/// either configured threshold is reached, trading a little latency for fewer writes on
+/// workloads dominated by small messages. With no thresholds configured, every frame is
+/// written through immediately, preserving today's latency characteristics.
+struct CoalescingWriter {
+    write_half: tokio::net::tcp::OwnedWriteHalf,
+    buffer: BytesMut,
+    buffered_messages: usize,
+    auto_flush_bytes: Option<usize>,
+    auto_flush_messages: Option<usize>,
+}
+
+impl CoalescingWriter {
+    fn new(
+        write_half: tokio::net::tcp::OwnedWriteHalf,
+        auto_flush_bytes: Option<usize>,
+        auto_flush_messages: Option<usize>,
+    ) -> Self {
+        CoalescingWriter {
+            write_half,
+            buffer: BytesMut::new(),
+            buffered_messages: 0,
+            auto_flush_bytes,
+            auto_flush_messages,
+        }
+    }
+
+    /// Writes a frame that is not subject to coalescing (control frames, explicit batches),
+    /// after first draining anything already queued so wire ordering is preserved.
+    async fn write_immediate(&mut self, frame: &[u8]) -> io::Result<()> {
+        self.drain().await?;
+        self.write_half.write_all(frame).await
+    }
+
+    /// Queues a PUB frame, flushing the accumulated buffer once a threshold is crossed.
+    async fn queue_publish(&mut self, frame: &[u8]) -> io::Result<()> {
+        if self.auto_flush_bytes.is_none() && self.auto_flush_messages.is_none() {
+            return self.write_half.write_all(frame).await;
+        }
+
+        self.buffer.extend_from_slice(frame);
+        self.buffered_messages += 1;
+
+        let bytes_threshold_hit = self
+            .auto_flush_bytes
+            .is_some_and(|threshold| self.buffer.len() >= threshold);
+        let messages_threshold_hit = self
+            .auto_flush_messages
+            .is_some_and(|threshold| self.buffered_messages >= threshold);
+
+        if bytes_threshold_hit || messages_threshold_hit {
+            self.drain().await?;
+        }
+        Ok(())
+    }
+
+    /// Writes out anything queued by [`CoalescingWriter::queue_publish`].
+    async fn drain(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.write_half.write_all(&self.buffer).await?;
+        self.buffer.clear();
+        self.buffered_messages = 0;
+        Ok(())
+    }
+}
+
+fn encode_publish(subject: &Subject, reply: Option<&Subject>, payload: &Bytes, frame: &mut BytesMut) {
+    frame.extend_from_slice(b"PUB ");
+    frame.extend_from_slice(subject.as_str().as_bytes());
+    frame.extend_from_slice(b" ");
+    if let Some(reply) = reply {
+        frame.extend_from_slice(reply.as_str().as_bytes());
+        frame.extend_from_slice(b" ");
+    }
+    frame.extend_from_slice(payload.len().to_string().as_bytes());
+    frame.extend_from_slice(b"\r\n");
+    frame.extend_from_slice(payload);
+    frame.extend_from_slice(b"\r\n");
+}
+
+async fn handle_command(
+    command: Command,
+    writer: &mut CoalescingWriter,
+    subscriptions: &mut HashMap<u64, mpsc::Sender<Message>>,
+    pending_flushes: &mut VecDeque<oneshot::Sender<()>>,
+) -> io::Result<()> {
+    match command {
+        Command::Publish {
+            subject,
+            payload,
+            reply,
+        } => {
+            let mut frame = BytesMut::new();
+            encode_publish(&subject, reply.as_ref(), &payload, &mut frame);
+            writer.queue_publish(&frame).await
+        }
+        Command::PublishBatch { messages } => {
+            let mut frame = BytesMut::new();
+            for (subject, payload) in &messages {
+                encode_publish(subject, None, payload, &mut frame);
+            }
+            writer.write_immediate(&frame).await
+        }
+        Command::Subscribe {
+            sid,
+            subject,
+            sender,
+        } => {
+            subscriptions.insert(sid, sender);
+            let frame = format!("SUB {} {}\r\n", subject.as_str(), sid);
+            writer.write_immediate(frame.as_bytes()).await
+        }
+        Command::Unsubscribe { sid, max_msgs } => {
+            if max_msgs.is_none() {
+                subscriptions.remove(&sid);
+            }
+            let frame = match max_msgs {
+                Some(max_msgs) => format!("UNSUB {sid} {max_msgs}\r\n"),
+                None => format!("UNSUB {sid}\r\n"),
+            };
+            writer.write_immediate(frame.as_bytes()).await
+        }
+        Command::Flush { result } => {
+            pending_flushes.push_back(result);
+            writer.write_immediate(b"PING\r\n").await
+        }
+    }
+}
+
+enum ServerOp {
+    Message { sid: u64, message: Message },
+    Ping,
+    Pong,
+}
+
+async fn read_server_op<R>(reader: &mut R) -> io::Result<Option<ServerOp>>
+where
+    R: tokio::io::AsyncBufRead + Unpin,
+{
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "PING" {
+            return Ok(Some(ServerOp::Ping));
+        }
+        if line == "PONG" {
+            return Ok(Some(ServerOp::Pong));
+        }
+        if let Some(rest) = line.strip_prefix("MSG ") {
+            return Ok(Some(read_payload(reader, rest, false).await?));
+        }
+        if let Some(rest) = line.strip_prefix("HMSG ") {
+            return Ok(Some(read_payload(reader, rest, true).await?));
+        }
+        // INFO, +OK and -ERR frames are not yet acted upon.
+    }
+}
+
+async fn read_payload<R>(reader: &mut R, header_line: &str, has_headers: bool) -> io::Result<ServerOp>
+where
+    R: tokio::io::AsyncBufRead + Unpin,
+{
+    let fields: Vec<&str> = header_line.split_whitespace().collect();
+    let (subject, sid, reply, header_len, total_len) = if has_headers {
+        match fields.as_slice() {
+            [subject, sid, hlen, tlen] => (*subject, *sid, None, *hlen, *tlen),
+            [subject, sid, reply, hlen, tlen] => (*subject, *sid, Some(*reply), *hlen, *tlen),
+            _ => return Err(invalid_data("malformed HMSG")),
+        }
+    } else {
+        match fields.as_slice() {
+            [subject, sid, len] => (*subject, *sid, None, "0", *len),
+            [subject, sid, reply, len] => (*subject, *sid, Some(*reply), "0", *len),
+            _ => return Err(invalid_data("malformed MSG")),
+        }
+    };
+
+    let header_len: usize = header_len.parse().map_err(|_| invalid_data("malformed byte count"))?;
+    let total_len: usize = total_len.parse().map_err(|_| invalid_data("malformed byte count"))?;
+
+    let mut buf = vec![0u8; total_len];
+    reader.read_exact(&mut buf).await?;
+    let mut crlf = [0u8; 2];
+    reader.read_exact(&mut crlf).await?;
+
+    let (status, description) = if header_len > 0 {
+        parse_status_header(&buf[..header_len])
+    } else {
+        (None, None)
+    };
+
+    let message = Message {
+        subject: Subject::from(subject.to_string()),
+        reply: reply.map(|reply| Subject::from(reply.to_string())),
+        payload: Bytes::copy_from_slice(&buf[header_len..total_len]),
+        status,
+        description,
+    };
+    let sid: u64 = sid.parse().map_err(|_| invalid_data("malformed sid"))?;
+
+    Ok(ServerOp::Message { sid, message })
+}
+
+fn parse_status_header(headers: &[u8]) -> (Option<StatusCode>, Option<String>) {
+    // The status line looks like "NATS/1.0 503 No Responders\r\n".
+    let text = String::from_utf8_lossy(headers);
+    let Some(status_line) = text.lines().next() else {
+        return (None, None);
+    };
+    let mut parts = status_line.splitn(3, ' ').skip(1);
+    let code = parts.next().and_then(|code| code.parse::<u16>().ok());
+    let description = parts.next().map(|description| description.trim().to_string());
+    (code.map(StatusCode), description)
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Errors returned by [`Client`](crate::Client) operations.
+#[derive(Debug)]
+pub enum Error {
+    /// The background connection task is no longer running.
+    ConnectionClosed,
+    /// The server reported that no responders were listening on the request subject.
+    NoResponders,
+    /// The operation did not complete before its deadline.
+    TimedOut,
+    /// An I/O error occurred while talking to the server.
+    Io(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ConnectionClosed => write!(f, "connection closed"),
+            Error::NoResponders => write!(f, "no responders"),
+            Error::TimedOut => write!(f, "timed out"),
+            Error::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// A `Result` alias defaulting to this crate's [`Error`] type.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,26 @@
+//! An async Rust client for the [NATS.io](https://nats.io) messaging system.
+
+mod client;
+mod connector;
+mod error;
+mod message;
+mod mux;
+mod options;
+mod request_many;
+mod subject;
+mod subscriber;
+
+pub use client::Client;
+pub use error::{Error, Result};
+pub use message::{Message, StatusCode};
+pub use options::ConnectOptions;
+pub use request_many::{RequestManyOptions, RequestManyStream};
+pub use subject::Subject;
+pub use subscriber::Subscriber;
+
+/// Connects to a NATS server using the default [`ConnectOptions`].
+///
+/// This is a shorthand for `ConnectOptions::new().connect(addr)`.
+pub async fn connect(addr: impl ToString) -> std::io::Result<Client> {
+    ConnectOptions::new().connect(addr).await
+}
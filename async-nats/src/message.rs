@@ -0,0 +1,22 @@
+use bytes::Bytes;
+
+use crate::subject::Subject;
+
+/// A message received from, or published to, the NATS server.
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub subject: Subject,
+    pub reply: Option<Subject>,
+    pub payload: Bytes,
+    pub status: Option<StatusCode>,
+    pub description: Option<String>,
+}
+
+/// A NATS protocol status code carried in a message's headers, e.g. the `503`
+/// "no responders" sentinel returned when a request subject has no subscriber.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusCode(pub u16);
+
+impl StatusCode {
+    pub const NO_RESPONDERS: StatusCode = StatusCode(503);
+}
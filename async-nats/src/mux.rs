@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures::StreamExt;
+use tokio::sync::oneshot;
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::message::Message;
+use crate::subject::Subject;
+
+/// A single shared reply inbox, demultiplexing replies to many in-flight requests by a
+/// per-request token appended to the inbox subject, so that firing a batch of requests
+/// doesn't need a SUB/UNSUB round trip per request. Used by [`Client::request_batch`] and
+/// [`Client::request_batch_with_timeout`].
+pub(crate) struct Mux {
+    prefix: Subject,
+    next_token: AtomicU64,
+    routes: Mutex<HashMap<u64, oneshot::Sender<Message>>>,
+}
+
+impl Mux {
+    /// Subscribes to a wildcard inbox and spawns the task that routes incoming replies to
+    /// whichever caller reserved the matching token.
+    pub(crate) async fn start(client: &Client) -> Result<Arc<Mux>> {
+        let prefix = client.new_inbox();
+        let wildcard = Subject::from(format!("{prefix}.*"));
+        let mut subscriber = client.subscribe(wildcard).await?;
+
+        let mux = Arc::new(Mux {
+            prefix,
+            next_token: AtomicU64::new(0),
+            routes: Mutex::new(HashMap::new()),
+        });
+
+        let router = mux.clone();
+        tokio::spawn(async move {
+            while let Some(message) = subscriber.next().await {
+                let Some(token) = router.token_of(&message.subject) else {
+                    continue;
+                };
+                if let Some(sender) = router.routes.lock().unwrap().remove(&token) {
+                    let _ = sender.send(message);
+                }
+            }
+            // The shared subscription ended because the connection dropped. Drop every
+            // outstanding sender so callers awaiting a reply see a closed channel instead of
+            // hanging forever, matching `Client::request`'s `None => Error::ConnectionClosed`.
+            router.routes.lock().unwrap().clear();
+        });
+
+        Ok(mux)
+    }
+
+    /// Reserves a fresh reply subject on this inbox, returning it along with the token
+    /// needed to [`Mux::cancel`] the reservation and the receiving half of the channel the
+    /// reply will be delivered on.
+    pub(crate) fn reserve(&self) -> (Subject, u64, oneshot::Receiver<Message>) {
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        self.routes.lock().unwrap().insert(token, sender);
+        (Subject::from(format!("{}.{token}", self.prefix)), token, receiver)
+    }
+
+    /// Drops a reservation that will never be delivered (the publish that would have
+    /// triggered a reply failed, or the caller gave up waiting), so it doesn't linger in
+    /// the routing table forever.
+    pub(crate) fn cancel(&self, token: u64) {
+        self.routes.lock().unwrap().remove(&token);
+    }
+
+    fn token_of(&self, subject: &Subject) -> Option<u64> {
+        subject
+            .as_str()
+            .strip_prefix(self.prefix.as_str())?
+            .strip_prefix('.')?
+            .parse()
+            .ok()
+    }
+}
@@ -0,0 +1,68 @@
+use std::io;
+
+use crate::client::Client;
+use crate::connector;
+
+/// Configuration used to establish a connection to a NATS server.
+#[derive(Clone, Debug)]
+pub struct ConnectOptions {
+    pub(crate) subscription_capacity: usize,
+    pub(crate) inbox_prefix: String,
+    pub(crate) auto_flush_bytes: Option<usize>,
+    pub(crate) auto_flush_messages: Option<usize>,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        ConnectOptions {
+            subscription_capacity: 65536,
+            inbox_prefix: "_INBOX".to_string(),
+            auto_flush_bytes: None,
+            auto_flush_messages: None,
+        }
+    }
+}
+
+impl ConnectOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the prefix used for the inboxes generated by [`Client::new_inbox`].
+    pub fn inbox_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.inbox_prefix = prefix.into();
+        self
+    }
+
+    /// Sets how many undelivered messages are buffered per-subscription before the
+    /// dispatcher applies backpressure to the server.
+    pub fn subscription_capacity(mut self, capacity: usize) -> Self {
+        self.subscription_capacity = capacity;
+        self
+    }
+
+    /// Coalesces outbound PUB frames into a single socket write once `bytes` have been
+    /// queued since the last write, trading latency for throughput on workloads dominated
+    /// by many small messages. Disabled (write-through) by default. Can be combined with
+    /// [`ConnectOptions::publish_auto_flush_messages`]; whichever threshold is reached
+    /// first triggers the write.
+    pub fn publish_auto_flush_bytes(mut self, bytes: usize) -> Self {
+        self.auto_flush_bytes = Some(bytes);
+        self
+    }
+
+    /// Coalesces outbound PUB frames into a single socket write once `messages` have been
+    /// queued since the last write, trading latency for throughput on workloads dominated
+    /// by many small messages. Disabled (write-through) by default. Can be combined with
+    /// [`ConnectOptions::publish_auto_flush_bytes`]; whichever threshold is reached first
+    /// triggers the write.
+    pub fn publish_auto_flush_messages(mut self, messages: usize) -> Self {
+        self.auto_flush_messages = Some(messages);
+        self
+    }
+
+    /// Connects to the server at `addr`, consuming these options.
+    pub async fn connect(self, addr: impl ToString) -> io::Result<Client> {
+        connector::connect(addr.to_string(), self).await
+    }
+}
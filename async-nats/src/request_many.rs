@@ -0,0 +1,224 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::Stream;
+use tokio::time::{sleep, Sleep};
+
+use crate::client::is_no_responders;
+use crate::message::Message;
+use crate::subscriber::Subscriber;
+
+/// Controls how a [`Client::request_many`](crate::Client::request_many) stream decides it has
+/// heard back from every responder it is going to hear from.
+#[derive(Debug, Clone)]
+pub struct RequestManyOptions {
+    /// Stop once this many replies have been yielded.
+    pub max_messages: Option<usize>,
+    /// Stop once this long has elapsed without a reply; reset on every message received.
+    pub idle_timeout: Option<Duration>,
+    /// Stop once this long has elapsed since the request was sent, regardless of how many
+    /// replies arrived in the meantime.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RequestManyOptions {
+    fn default() -> Self {
+        RequestManyOptions {
+            max_messages: None,
+            idle_timeout: Some(Duration::from_millis(300)),
+            timeout: None,
+        }
+    }
+}
+
+/// A stream of replies to a [`Client::request_many`](crate::Client::request_many) call.
+///
+/// The stream ends when any configured limit is reached, or immediately upon receiving the
+/// server's "no responders" sentinel.
+pub struct RequestManyStream {
+    subscriber: Subscriber,
+    max_messages: Option<usize>,
+    idle_timeout: Option<Duration>,
+    received: usize,
+    idle: Option<Pin<Box<Sleep>>>,
+    deadline: Option<Pin<Box<Sleep>>>,
+}
+
+impl RequestManyStream {
+    pub(crate) fn new(subscriber: Subscriber, options: RequestManyOptions) -> Self {
+        RequestManyStream {
+            subscriber,
+            max_messages: options.max_messages,
+            idle_timeout: options.idle_timeout,
+            received: 0,
+            idle: options.idle_timeout.map(|duration| Box::pin(sleep(duration))),
+            deadline: options.timeout.map(|duration| Box::pin(sleep(duration))),
+        }
+    }
+}
+
+impl Stream for RequestManyStream {
+    type Item = Message;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(max_messages) = self.max_messages {
+            if self.received >= max_messages {
+                return Poll::Ready(None);
+            }
+        }
+
+        // Poll the subscriber before the idle/deadline timers: a reply that is already
+        // sitting in the channel must be yielded even if a timer also fired on this same
+        // poll, or it would be silently dropped the instant the stream ends.
+        match Pin::new(&mut self.subscriber).poll_next(cx) {
+            Poll::Ready(Some(message)) if is_no_responders(&message) => return Poll::Ready(None),
+            Poll::Ready(Some(message)) => {
+                self.received += 1;
+                if let Some(duration) = self.idle_timeout {
+                    self.idle = Some(Box::pin(sleep(duration)));
+                }
+                return Poll::Ready(Some(message));
+            }
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => {}
+        }
+
+        if let Some(deadline) = self.deadline.as_mut() {
+            if deadline.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(None);
+            }
+        }
+        if let Some(idle) = self.idle.as_mut() {
+            if idle.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(None);
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use tokio::sync::mpsc;
+
+    use crate::client::Command;
+    use crate::message::{Message, StatusCode};
+    use crate::subject::Subject;
+    use crate::subscriber::Subscriber;
+
+    use super::*;
+
+    fn fake_subscriber() -> (mpsc::Sender<Message>, Subscriber) {
+        let (command_sender, _commands) = mpsc::channel::<Command>(1);
+        let (message_sender, message_receiver) = mpsc::channel(8);
+        (message_sender, Subscriber::new(1, command_sender, message_receiver))
+    }
+
+    fn fake_message() -> Message {
+        Message {
+            subject: Subject::from("reply".to_string()),
+            reply: None,
+            payload: bytes::Bytes::new(),
+            status: None,
+            description: None,
+        }
+    }
+
+    fn no_responders_message() -> Message {
+        Message {
+            status: Some(StatusCode::NO_RESPONDERS),
+            ..fake_message()
+        }
+    }
+
+    // Regression test for a bug where the idle/deadline timers were checked before the
+    // subscriber, so a reply already sitting in the channel at the moment the idle timer
+    // fired was silently dropped instead of yielded.
+    #[tokio::test(start_paused = true)]
+    async fn yields_message_that_arrives_as_idle_timer_fires() {
+        let (sender, subscriber) = fake_subscriber();
+        let mut stream = RequestManyStream::new(
+            subscriber,
+            RequestManyOptions {
+                max_messages: None,
+                idle_timeout: Some(Duration::from_millis(10)),
+                timeout: None,
+            },
+        );
+
+        sender.try_send(fake_message()).unwrap();
+        tokio::time::advance(Duration::from_millis(10)).await;
+
+        assert!(stream.next().await.is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_timeout_ends_stream_when_no_message_arrives() {
+        let (_sender, subscriber) = fake_subscriber();
+        let mut stream = RequestManyStream::new(
+            subscriber,
+            RequestManyOptions {
+                max_messages: None,
+                idle_timeout: Some(Duration::from_millis(10)),
+                timeout: None,
+            },
+        );
+
+        tokio::time::advance(Duration::from_millis(10)).await;
+
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stops_once_max_messages_is_reached() {
+        let (sender, subscriber) = fake_subscriber();
+        let mut stream = RequestManyStream::new(
+            subscriber,
+            RequestManyOptions {
+                max_messages: Some(1),
+                idle_timeout: None,
+                timeout: None,
+            },
+        );
+
+        sender.try_send(fake_message()).unwrap();
+        sender.try_send(fake_message()).unwrap();
+
+        assert!(stream.next().await.is_some());
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stops_once_overall_timeout_elapses_even_with_replies_still_arriving() {
+        let (sender, subscriber) = fake_subscriber();
+        let mut stream = RequestManyStream::new(
+            subscriber,
+            RequestManyOptions {
+                max_messages: None,
+                idle_timeout: None,
+                timeout: Some(Duration::from_millis(10)),
+            },
+        );
+
+        sender.try_send(fake_message()).unwrap();
+        assert!(stream.next().await.is_some());
+
+        tokio::time::advance(Duration::from_millis(10)).await;
+
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stops_immediately_on_no_responders() {
+        let (sender, subscriber) = fake_subscriber();
+        let mut stream = RequestManyStream::new(subscriber, RequestManyOptions::default());
+
+        sender.try_send(no_responders_message()).unwrap();
+
+        assert!(stream.next().await.is_none());
+    }
+}
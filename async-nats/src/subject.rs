@@ -0,0 +1,36 @@
+use std::borrow::Borrow;
+use std::fmt;
+
+/// A NATS subject, e.g. `foo.bar.*`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Subject(String);
+
+impl Subject {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Subject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for Subject {
+    fn from(subject: String) -> Self {
+        Subject(subject)
+    }
+}
+
+impl From<&str> for Subject {
+    fn from(subject: &str) -> Self {
+        Subject(subject.to_owned())
+    }
+}
+
+impl Borrow<str> for Subject {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
@@ -0,0 +1,69 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+use tokio::sync::mpsc;
+
+use crate::client::Command;
+use crate::error::{Error, Result};
+use crate::message::Message;
+
+/// A subscription to a subject, yielding every message published to it as a [`Stream`].
+///
+/// Dropping a `Subscriber` unsubscribes it from the server.
+pub struct Subscriber {
+    pub(crate) sid: u64,
+    pub(crate) sender: mpsc::Sender<Command>,
+    pub(crate) receiver: mpsc::Receiver<Message>,
+}
+
+impl Subscriber {
+    pub(crate) fn new(
+        sid: u64,
+        sender: mpsc::Sender<Command>,
+        receiver: mpsc::Receiver<Message>,
+    ) -> Self {
+        Subscriber {
+            sid,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Unsubscribes after `max_msgs` more messages have been delivered.
+    pub async fn unsubscribe_after(&self, max_msgs: u64) -> Result<()> {
+        self.sender
+            .send(Command::Unsubscribe {
+                sid: self.sid,
+                max_msgs: Some(max_msgs),
+            })
+            .await
+            .map_err(|_| Error::ConnectionClosed)
+    }
+}
+
+impl Stream for Subscriber {
+    type Item = Message;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        // try_send alone would silently leak the sid in the connector's subscriptions map if
+        // the command channel is ever full at drop time, since there is no later retry. Spawn
+        // the send instead so backpressure makes it wait rather than drop the unsubscribe.
+        let sender = self.sender.clone();
+        let sid = self.sid;
+        tokio::spawn(async move {
+            let _ = sender
+                .send(Command::Unsubscribe {
+                    sid,
+                    max_msgs: None,
+                })
+                .await;
+        });
+    }
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::StreamExt;
+
+/// Requires a `nats-server` binary on `PATH`; see `nats_server::run_basic_server`.
+#[tokio::test]
+async fn partial_completion_preserves_already_finished_requests() {
+    let server = nats_server::run_basic_server();
+    let client = async_nats::connect(server.client_url()).await.unwrap();
+
+    let responder = client.clone();
+    tokio::spawn(async move {
+        let mut subscriber = responder.subscribe("batch.partial".into()).await.unwrap();
+        while let Some(message) = subscriber.next().await {
+            let reply = message.reply.unwrap();
+            if message.payload.as_ref() == b"slow" {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            }
+            responder.publish(reply, Bytes::new()).await.unwrap();
+            responder.flush().await.unwrap();
+        }
+    });
+
+    // Give the responder time to subscribe before the batch goes out.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let results = client
+        .request_batch_with_timeout(
+            vec![
+                ("batch.partial".into(), Bytes::from_static(b"fast")),
+                ("batch.partial".into(), Bytes::from_static(b"slow")),
+            ],
+            Duration::from_millis(300),
+        )
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok(), "fast request should complete before the deadline");
+    assert!(
+        matches!(results[1], Err(async_nats::Error::TimedOut)),
+        "slow request should time out instead of being silently dropped"
+    );
+}
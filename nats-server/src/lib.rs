@@ -0,0 +1,44 @@
+//! Test-only helper for spawning a local `nats-server` process for benchmarks and
+//! integration tests.
+
+use std::net::TcpListener;
+use std::process::{Child, Command};
+use std::time::Duration;
+
+/// A `nats-server` process bound to an ephemeral port, killed when dropped.
+pub struct Server {
+    child: Child,
+    port: u16,
+}
+
+impl Server {
+    /// Returns the address clients should connect to.
+    pub fn client_url(&self) -> String {
+        format!("127.0.0.1:{}", self.port)
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+/// Spawns a `nats-server` binary (expected to be on `PATH`) listening on an ephemeral
+/// port, waiting briefly for it to come up before returning.
+pub fn run_basic_server() -> Server {
+    let port = TcpListener::bind("127.0.0.1:0")
+        .expect("failed to reserve a port")
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let child = Command::new("nats-server")
+        .args(["-p", &port.to_string()])
+        .spawn()
+        .expect("failed to spawn nats-server; is it installed and on PATH?");
+
+    std::thread::sleep(Duration::from_millis(500));
+
+    Server { child, port }
+}